@@ -0,0 +1,128 @@
+//! A small fixed-growth bitset used to track transitive reachability
+//! between nodes without pulling in a crate for it.
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A growable set of bits, stored as `u64` words.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct BitVector {
+    words: Vec<u64>,
+}
+
+impl BitVector {
+    pub(crate) fn new() -> Self {
+        Self { words: vec![] }
+    }
+
+    pub(crate) fn insert(&mut self, bit: usize) {
+        let word = bit / WORD_BITS;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (bit % WORD_BITS);
+    }
+
+    pub(crate) fn contains(&self, bit: usize) -> bool {
+        let word = bit / WORD_BITS;
+        self.words
+            .get(word)
+            .is_some_and(|w| w & (1 << (bit % WORD_BITS)) != 0)
+    }
+
+    /// ORs `other` into `self`, growing as needed. Returns true if any bit
+    /// in `self` changed as a result.
+    pub(crate) fn union_into(&mut self, other: &BitVector) -> bool {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        let mut changed = false;
+        for (word, other_word) in self.words.iter_mut().zip(other.words.iter()) {
+            let merged = *word | other_word;
+            if merged != *word {
+                *word = merged;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// One `BitVector` row per node, addressed by a dense `usize` index since
+/// `SlotMap` keys aren't dense.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct BitMatrix {
+    rows: Vec<BitVector>,
+    /// Indices freed by `remove_row`, reused by the next `push_row` so
+    /// pruning a subtree doesn't leak rows forever.
+    free: Vec<usize>,
+}
+
+impl BitMatrix {
+    pub(crate) fn new() -> Self {
+        Self {
+            rows: vec![],
+            free: vec![],
+        }
+    }
+
+    /// Adds a new empty row (reusing a freed index if one is available)
+    /// and returns its index.
+    pub(crate) fn push_row(&mut self) -> usize {
+        if let Some(index) = self.free.pop() {
+            self.rows[index] = BitVector::new();
+            index
+        } else {
+            self.rows.push(BitVector::new());
+            self.rows.len() - 1
+        }
+    }
+
+    /// Clears `index`'s row and marks it free for reuse by a later
+    /// `push_row`. Other rows may still carry a stale bit for `index` —
+    /// removing a node can open up indirect paths that used to run
+    /// through it, so a single row can't be patched locally. Callers must
+    /// follow up with a full recompute of affected rows (see
+    /// `Sequencer::rebuild_reach`) to keep reachability correct.
+    pub(crate) fn remove_row(&mut self, index: usize) {
+        self.rows[index] = BitVector::new();
+        self.free.push(index);
+    }
+
+    pub(crate) fn row(&self, index: usize) -> &BitVector {
+        &self.rows[index]
+    }
+
+    pub(crate) fn row_mut(&mut self, index: usize) -> &mut BitVector {
+        &mut self.rows[index]
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_contains() {
+        let mut bv = BitVector::new();
+        assert!(!bv.contains(130));
+        bv.insert(130);
+        assert!(bv.contains(130));
+        assert!(!bv.contains(129));
+    }
+
+    #[test]
+    fn test_union_into_reports_change() {
+        let mut a = BitVector::new();
+        let mut b = BitVector::new();
+        b.insert(5);
+        assert!(a.union_into(&b));
+        assert!(!a.union_into(&b));
+        assert!(a.contains(5));
+    }
+}
@@ -0,0 +1,184 @@
+//! Drives [`Sequencer`] items that are themselves futures, so independent
+//! branches of the dependency graph run concurrently without a manual
+//! `drain_queue`/`node_finished` loop.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::{SeqKey, Sequencer};
+
+/// Drives a [`Sequencer`] whose items are futures (`I: Future<Output = ()>`)
+/// to completion. Each poll drains newly-ready nodes and polls the running
+/// nodes until one resolves; that node is yielded straight away (the rest
+/// are polled on the next call), and [`Sequencer::node_finished`] is called
+/// automatically, queuing its ready children for the next poll.
+///
+/// Implements `Stream<Item = SeqKey>`, yielding a node's key as its future
+/// completes, so it can be driven with `.await` (e.g. via `StreamExt::for_each`
+/// or [`SequencerExecutor::run`]). `sequencer_mut` stays available for
+/// splicing in new sub-sequences (e.g. `inject_child_seq`) while others are
+/// already executing.
+pub struct SequencerExecutor<I>
+where
+    I: Future<Output = ()> + Unpin,
+{
+    sequencer: Sequencer<I>,
+    running: Vec<SeqKey>,
+}
+
+impl<I> SequencerExecutor<I>
+where
+    I: Future<Output = ()> + Unpin,
+{
+    pub fn new(sequencer: Sequencer<I>) -> Self {
+        Self {
+            sequencer,
+            running: vec![],
+        }
+    }
+
+    /// Mutable access to the wrapped sequencer, for splicing in new
+    /// sub-sequences mid-run.
+    pub fn sequencer_mut(&mut self) -> &mut Sequencer<I> {
+        &mut self.sequencer
+    }
+
+    /// Unwraps the executor, returning the sequencer once the graph has
+    /// finished (or to inspect/resume it otherwise).
+    pub fn into_inner(self) -> Sequencer<I> {
+        self.sequencer
+    }
+
+    /// Drives every node to completion, returning the sequencer afterwards.
+    pub async fn run(mut self) -> Sequencer<I> {
+        use futures_util::StreamExt;
+        while self.next().await.is_some() {}
+        self.sequencer
+    }
+}
+
+impl<I> Stream for SequencerExecutor<I>
+where
+    I: Future<Output = ()> + Unpin,
+{
+    type Item = SeqKey;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        let running = &mut this.running;
+        this.sequencer.drain_queue(|key, _item| running.push(key));
+
+        for i in (0..this.running.len()).rev() {
+            let key = this.running[i];
+            let item = this.sequencer.item_mut(key);
+            if Pin::new(item).poll(cx).is_ready() {
+                this.running.remove(i);
+                this.sequencer.node_finished(key);
+                return Poll::Ready(Some(key));
+            }
+        }
+
+        if this.running.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    use super::*;
+    use crate::Sequencer;
+
+    /// A future that stays `Pending` for `remaining` polls before resolving.
+    struct Countdown {
+        remaining: u32,
+    }
+
+    impl Future for Countdown {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.remaining == 0 {
+                Poll::Ready(())
+            } else {
+                self.remaining -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn noop_waker() -> Waker {
+        Waker::from(Arc::new(NoopWake))
+    }
+
+    #[test]
+    fn test_concurrent_branches_complete_out_of_order() {
+        let mut sequencer = Sequencer::default();
+        // `slow` is queued first but resolves after `fast`, so the executor
+        // must yield them out of queueing order.
+        let slow = sequencer.new_node(Countdown { remaining: 2 });
+        let fast = sequencer.new_node(Countdown { remaining: 0 });
+        let mut executor = SequencerExecutor::new(sequencer);
+
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut order = vec![];
+        loop {
+            match Pin::new(&mut executor).poll_next(&mut cx) {
+                Poll::Ready(Some(key)) => order.push(key),
+                Poll::Ready(None) => break,
+                Poll::Pending => {}
+            }
+        }
+
+        assert_eq!(vec![fast, slow], order);
+    }
+
+    #[test]
+    fn test_inject_child_seq_mid_stream() {
+        let mut sequencer = Sequencer::default();
+        // Not yet resolved, so `root` is still running when we splice in.
+        let root = sequencer.new_node(Countdown { remaining: 1 });
+        let mut executor = SequencerExecutor::new(sequencer);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        // First poll just decrements the countdown; `root` is still running.
+        assert_eq!(Poll::Pending, Pin::new(&mut executor).poll_next(&mut cx));
+
+        // Splice a new child sequence onto the still-running root.
+        let injected = executor
+            .sequencer_mut()
+            .inject_child_seq(root, vec![Countdown { remaining: 0 }]);
+
+        // `root` resolves next, which should queue the injected node.
+        assert_eq!(
+            Poll::Ready(Some(root)),
+            Pin::new(&mut executor).poll_next(&mut cx)
+        );
+        assert_eq!(
+            Poll::Ready(Some(injected)),
+            Pin::new(&mut executor).poll_next(&mut cx)
+        );
+        assert_eq!(
+            Poll::Ready(None),
+            Pin::new(&mut executor).poll_next(&mut cx)
+        );
+    }
+}
@@ -1,28 +1,82 @@
 use std::collections::HashSet;
+use std::fmt;
 
-use slotmap::{new_key_type, SlotMap};
+use slotmap::{new_key_type, SecondaryMap, SlotMap};
+
+use crate::bitset::{BitMatrix, BitVector};
+
+mod bitset;
+#[cfg(feature = "futures")]
+mod executor;
+
+#[cfg(feature = "futures")]
+pub use executor::SequencerExecutor;
 
 new_key_type! {
     pub struct SeqKey;
 }
 
+/// Returned by [`Sequencer::add_edge`] when the edge would create a cycle.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleError;
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "edge would create a cycle in the sequence graph")
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NodeStatus {
     Active,
     Inactive,
     Completed,
+    /// Cancelled via [`Sequencer::cancel`] or [`Sequencer::cancel_subtree`].
+    Cancelled,
+    /// Not selected by [`Sequencer::node_finished_with`]. Counts the same
+    /// as `Completed` for a downstream join's readiness check, so a
+    /// skipped branch doesn't stall nodes that depend on it.
+    Skipped,
+}
+
+/// Controls how a node with multiple parents decides it's ready to run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinMode {
+    /// Ready once every parent is `Completed` or `Skipped` (the default).
+    #[default]
+    All,
+    /// Ready as soon as any one parent is `Completed`, e.g. for "race"
+    /// patterns where only the first of several branches matters.
+    Any,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SeqNode<I> {
     pub key: SeqKey,
     parents: Vec<SeqKey>,
     children: Vec<SeqKey>,
     status: NodeStatus,
+    join: JoinMode,
+    /// Set once this node has been queued, so an `Any`-join node isn't
+    /// queued again when its other parents later complete.
+    queued: bool,
     pub item: I,
 }
 
+/// With the `serde` feature enabled, `Sequencer<I>` (for `I: Serialize +
+/// DeserializeOwned`) is directly `Serialize`/`Deserialize` — hand it to a
+/// serializer (`serde_json`, `bincode`, ...) to persist an in-progress
+/// sequence and hand the deserialized value straight back to pick up where
+/// it left off. `SeqKey`s round-trip through the `SlotMap`'s own `serde`
+/// support, so keys handed out before serializing still reference the
+/// right nodes afterwards.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sequencer<I> {
     /// All root nodes
     roots: Vec<SeqKey>,
@@ -32,6 +86,11 @@ pub struct Sequencer<I> {
     queued_nodes: Vec<SeqKey>,
     /// List of all nodes that are currently running
     active_nodes: HashSet<SeqKey>,
+    /// Dense row index for each node, since `SlotMap` keys aren't dense
+    node_index: SecondaryMap<SeqKey, usize>,
+    /// `reach[u]` holds the set of row indices reachable from node `u`,
+    /// used by `add_edge` to reject edges that would form a cycle
+    reach: BitMatrix,
 }
 
 impl<T> Default for Sequencer<T> {
@@ -42,27 +101,137 @@ impl<T> Default for Sequencer<T> {
             nodes,
             queued_nodes: vec![],
             active_nodes: HashSet::new(),
+            node_index: SecondaryMap::new(),
+            reach: BitMatrix::new(),
         }
     }
 }
 
 impl<I> Sequencer<I> {
     fn create_node(&mut self, item: I) -> SeqKey {
-        self.nodes.insert_with_key(|key| SeqNode {
+        self.create_node_with_join(item, JoinMode::All)
+    }
+
+    fn create_node_with_join(&mut self, item: I, join: JoinMode) -> SeqKey {
+        let key = self.nodes.insert_with_key(|key| SeqNode {
             key,
             parents: vec![],
             children: vec![],
             status: NodeStatus::Inactive,
+            join,
+            queued: false,
             item,
-        })
+        });
+        let index = self.reach.push_row();
+        self.node_index.insert(key, index);
+        key
     }
 
     fn create_node_with_parents(&mut self, parents: Vec<SeqKey>, item: I) -> SeqKey {
-        let key = self.create_node(item);
-        for pkey in parents.iter().copied() {
-            self.nodes[pkey].children.push(key);
+        self.create_node_with_parents_and_join(parents, item, JoinMode::All)
+    }
+
+    fn create_node_with_parents_and_join(
+        &mut self,
+        parents: Vec<SeqKey>,
+        item: I,
+        join: JoinMode,
+    ) -> SeqKey {
+        let key = self.create_node_with_join(item, join);
+        for pkey in parents {
+            self.add_edge(pkey, key)
+                .expect("a freshly created node cannot introduce a cycle");
+        }
+        key
+    }
+
+    /// Wires `parent` as a parent of `child`, rejecting the edge if `child`
+    /// already (transitively) reaches `parent`: nodes on a cycle would wait
+    /// on each other forever in `queue_ready_children`. All of the seq builders route
+    /// through this so that arbitrary rewiring of existing `SeqKey`s can
+    /// never introduce a cycle.
+    pub fn add_edge(&mut self, parent: SeqKey, child: SeqKey) -> Result<(), CycleError> {
+        if parent == child {
+            return Err(CycleError);
+        }
+
+        let u = self.node_index[parent];
+        let v = self.node_index[child];
+
+        // u -> v would create a cycle if v can already reach u.
+        if self.reach.row(v).contains(u) {
+            return Err(CycleError);
+        }
+
+        self.nodes[parent].children.push(child);
+        self.nodes[child].parents.push(parent);
+        self.propagate_reach(u, v);
+        Ok(())
+    }
+
+    /// Records that node `u` reaches node `v` (and transitively everything
+    /// `v` reaches), then propagates the updated reach set to every
+    /// ancestor that already reaches `u`, iterating to a fixpoint.
+    fn propagate_reach(&mut self, u: usize, v: usize) {
+        self.reach.row_mut(u).insert(v);
+        let v_reach = self.reach.row(v).clone();
+        self.reach.row_mut(u).union_into(&v_reach);
+
+        loop {
+            let u_reach = self.reach.row(u).clone();
+            let mut changed = false;
+            for w in 0..self.reach.len() {
+                if w != u
+                    && self.reach.row(w).contains(u)
+                    && self.reach.row_mut(w).union_into(&u_reach)
+                {
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Recomputes every live node's reach row from scratch by walking its
+    /// current `children` edges. Needed after removing nodes (e.g.
+    /// `cancel_subtree`): deleting a node can open up an apparent "no
+    /// longer reachable" path for nodes that used to reach something only
+    /// *through* it, and patching individual rows in place can't account
+    /// for that, so a full rebuild from the live edge list is the only
+    /// thing that's reliably correct.
+    fn rebuild_reach(&mut self) {
+        for key in self.nodes.keys().collect::<Vec<_>>() {
+            let index = self.node_index[key];
+
+            let mut descendants = BitVector::new();
+            let mut seen: HashSet<SeqKey> = HashSet::new();
+            let mut frontier = self.nodes[key].children.clone();
+            while let Some(ckey) = frontier.pop() {
+                if !seen.insert(ckey) {
+                    continue;
+                }
+                descendants.insert(self.node_index[ckey]);
+                frontier.extend(self.nodes[ckey].children.iter().copied());
+            }
+
+            *self.reach.row_mut(index) = descendants;
+        }
+    }
+
+    /// Inserts a new node with the given parents and join mode, queuing it
+    /// immediately if it's already ready (e.g. it has no parents, or it's
+    /// an `Any`-join and a parent is already `Completed`).
+    /// Returns the node's key.
+    pub fn new_node_with_join(&mut self, parents: Vec<SeqKey>, item: I, join: JoinMode) -> SeqKey {
+        let key = self.create_node_with_parents_and_join(parents.clone(), item, join);
+        if parents.is_empty() {
+            self.roots.push(key);
+            self.queue_node(key);
+        } else if self.is_node_ready(key) {
+            self.queue_node(key);
         }
-        self.nodes[key].parents = parents;
         key
     }
 
@@ -72,7 +241,7 @@ impl<I> Sequencer<I> {
     pub fn new_node(&mut self, item: I) -> SeqKey {
         let key = self.create_node(item);
         self.roots.push(key);
-        self.queued_nodes.push(key);
+        self.queue_node(key);
         key
     }
 
@@ -112,31 +281,64 @@ impl<I> Sequencer<I> {
     /// Returns the key of the last node in the sequence.
     pub fn inject_child_seq(&mut self, parent: SeqKey, items: Vec<I>) -> SeqKey {
         // Detach children from parent
-        let mut parent_children = std::mem::take(&mut self.nodes[parent].children);
+        let parent_children = std::mem::take(&mut self.nodes[parent].children);
 
         // Inject the new sequence
         let last_key = self.new_child_seq(parent, items);
 
-        // Insert the parent's ex-children to the last node in the new seq
-        self.nodes[last_key].children.append(&mut parent_children);
+        // Re-parent the detached children onto the last node of the new
+        // seq, keeping their `parents` bookkeeping and the reachability
+        // matrix consistent.
+        for child in parent_children {
+            if let Some(pos) = self.nodes[child].parents.iter().position(|p| *p == parent) {
+                self.nodes[child].parents[pos] = last_key;
+            }
+            self.nodes[last_key].children.push(child);
+            let u = self.node_index[last_key];
+            let v = self.node_index[child];
+            self.propagate_reach(u, v);
+        }
 
         last_key
     }
 
-    /// Queue all children of node with completed parents
-    fn queue_ready_children(&mut self, key: SeqKey) {
+    /// Returns true if `key`'s join condition is satisfied by its parents'
+    /// current statuses and it hasn't already been queued.
+    fn is_node_ready(&self, key: SeqKey) -> bool {
         let node = &self.nodes[key];
-        'child: for ckey in node.children.iter().copied() {
-            let cnode = &self.nodes[ckey];
-            // Check that all parents are completed
-            for pkey in cnode.parents.iter().copied() {
-                let pnode = &self.nodes[pkey];
-                if pnode.status != NodeStatus::Completed {
-                    continue 'child;
-                }
+        if node.queued || node.status != NodeStatus::Inactive {
+            return false;
+        }
+        match node.join {
+            JoinMode::All => node
+                .parents
+                .iter()
+                .all(|pkey| Self::parent_satisfied(self.nodes[*pkey].status)),
+            JoinMode::Any => node
+                .parents
+                .iter()
+                .any(|pkey| Self::parent_satisfied(self.nodes[*pkey].status)),
+        }
+    }
+
+    /// A parent satisfies a child's join condition once it's `Completed`,
+    /// or `Skipped` (an unselected branch doesn't stall a downstream join).
+    fn parent_satisfied(status: NodeStatus) -> bool {
+        matches!(status, NodeStatus::Completed | NodeStatus::Skipped)
+    }
+
+    fn queue_node(&mut self, key: SeqKey) {
+        self.nodes[key].queued = true;
+        self.queued_nodes.push(key);
+    }
+
+    /// Queue all children of node whose join condition is now satisfied
+    fn queue_ready_children(&mut self, key: SeqKey) {
+        let children = self.nodes[key].children.clone();
+        for ckey in children {
+            if self.is_node_ready(ckey) {
+                self.queue_node(ckey);
             }
-            // If so queue the child node
-            self.queued_nodes.push(ckey);
         }
     }
 
@@ -147,6 +349,148 @@ impl<I> Sequencer<I> {
         self.queue_ready_children(key);
     }
 
+    /// Mark that a node is finished executing, choosing which of its
+    /// children actually activate. `selector` is called once per child
+    /// with that child's current node; children it rejects are marked
+    /// `Skipped` instead of being queued, and a skipped parent still
+    /// satisfies a downstream join so the graph doesn't stall on it. This
+    /// turns the DAG into a branching state machine, e.g. for routing on
+    /// a node's outcome (success/failure, a dialogue choice, ...).
+    pub fn node_finished_with<F>(&mut self, key: SeqKey, mut selector: F)
+    where
+        F: FnMut(&SeqNode<I>) -> bool,
+    {
+        self.set_node_status(key, NodeStatus::Completed);
+        let children = self.nodes[key].children.clone();
+        for ckey in children {
+            if !selector(&self.nodes[ckey]) {
+                self.skip_node(ckey);
+            }
+        }
+        self.queue_ready_children(key);
+    }
+
+    /// Marks `key` as `Skipped`, then handles its children: a child whose
+    /// join is now satisfied thanks to at least one genuinely `Completed`
+    /// parent is queued as usual (e.g. a diamond where the other path
+    /// really ran), but a child with no completed parent at all — every
+    /// path into it is `Skipped`/`Cancelled` — is cascaded into `Skipped`
+    /// too instead of being queued, the same way `cancel_subtree` only
+    /// prunes a node once *all* of its parents are cancelled. Without this,
+    /// an unselected branch's own children would still run for real,
+    /// defeating the whole point of routing on `node_finished_with`.
+    ///
+    /// A no-op if the node has already been queued or is no longer
+    /// `Inactive`: it may have become ready through a different parent
+    /// (e.g. an `Any`-join, or a diamond made of two `All`-joined paths),
+    /// in which case it's genuinely running (or about to run) and must
+    /// not be clobbered.
+    fn skip_node(&mut self, key: SeqKey) {
+        let node = &self.nodes[key];
+        if node.queued || node.status != NodeStatus::Inactive {
+            return;
+        }
+        self.nodes[key].status = NodeStatus::Skipped;
+
+        let children = self.nodes[key].children.clone();
+        for ckey in children {
+            if !self.is_node_ready(ckey) {
+                continue;
+            }
+            if self.has_completed_parent(ckey) {
+                self.queue_node(ckey);
+            } else if self.nodes[ckey].parents.iter().all(|pkey| {
+                matches!(
+                    self.nodes[*pkey].status,
+                    NodeStatus::Skipped | NodeStatus::Cancelled
+                )
+            }) {
+                self.skip_node(ckey);
+            }
+        }
+    }
+
+    /// True if any of `key`'s parents actually ran to completion, as
+    /// opposed to being `Skipped`/`Cancelled`.
+    fn has_completed_parent(&self, key: SeqKey) -> bool {
+        self.nodes[key]
+            .parents
+            .iter()
+            .any(|pkey| self.nodes[*pkey].status == NodeStatus::Completed)
+    }
+
+    /// Marks a single node as `Cancelled` and removes it from the active
+    /// and queued sets, without touching the rest of the graph. A
+    /// `Cancelled` parent is never `Completed`, so an `All`-join depending
+    /// on it simply blocks; use [`Sequencer::cancel_subtree`] to prune a
+    /// whole branch instead.
+    pub fn cancel(&mut self, key: SeqKey) {
+        self.nodes[key].status = NodeStatus::Cancelled;
+        self.active_nodes.remove(&key);
+        self.queued_nodes.retain(|k| *k != key);
+    }
+
+    /// Cancels `key` and recursively prunes every descendant whose parents
+    /// are now entirely within the cancelled set, dropping them from
+    /// `nodes`, `roots` and the queues. A descendant reachable from
+    /// outside the cancelled branch survives, so shared dependencies
+    /// aren't ripped out from under other live nodes. This lets game code
+    /// abort a branch (e.g. "skip dialogue") without leaking orphaned
+    /// nodes or leaving nodes queued or active that can never finish.
+    pub fn cancel_subtree(&mut self, key: SeqKey) {
+        self.cancel(key);
+
+        let mut cancelled: HashSet<SeqKey> = HashSet::new();
+        cancelled.insert(key);
+        let mut frontier: Vec<SeqKey> = self.nodes[key].children.clone();
+
+        while let Some(ckey) = frontier.pop() {
+            if cancelled.contains(&ckey) {
+                continue;
+            }
+            let all_parents_cancelled = self.nodes[ckey]
+                .parents
+                .iter()
+                .all(|pkey| cancelled.contains(pkey));
+            if all_parents_cancelled {
+                cancelled.insert(ckey);
+                frontier.extend(self.nodes[ckey].children.iter().copied());
+            }
+        }
+
+        // Detach references to the pruned nodes from anything that survives.
+        for &ckey in &cancelled {
+            let parents = self.nodes[ckey].parents.clone();
+            for pkey in parents {
+                if !cancelled.contains(&pkey) {
+                    self.nodes[pkey].children.retain(|c| *c != ckey);
+                }
+            }
+            let children = self.nodes[ckey].children.clone();
+            for chkey in children {
+                if !cancelled.contains(&chkey) {
+                    self.nodes[chkey].parents.retain(|p| *p != ckey);
+                }
+            }
+        }
+
+        for ckey in cancelled {
+            self.roots.retain(|r| *r != ckey);
+            self.queued_nodes.retain(|q| *q != ckey);
+            self.active_nodes.remove(&ckey);
+            self.nodes.remove(ckey);
+            if let Some(index) = self.node_index.remove(ckey) {
+                self.reach.remove_row(index);
+            }
+        }
+
+        // Removing nodes can make a surviving node's old reach bits stale
+        // (paths that used to run through a pruned node), so patch up the
+        // whole matrix from the live edge list rather than the individual
+        // rows touched above.
+        self.rebuild_reach();
+    }
+
     fn set_node_status(&mut self, key: SeqKey, new_status: NodeStatus) {
         let node = &mut self.nodes[key];
         match (node.status, new_status) {
@@ -179,6 +523,14 @@ impl<I> Sequencer<I> {
     pub fn iter_active(&self) -> impl Iterator<Item = &SeqNode<I>> {
         self.active_nodes.iter().map(|key| &self.nodes[*key])
     }
+
+    /// Mutable access to a node's item, for drivers (e.g. [`SequencerExecutor`])
+    /// that need to poll it in place.
+    #[cfg(feature = "futures")]
+    pub(crate) fn item_mut(&mut self, key: SeqKey) -> &mut I {
+        &mut self.nodes[key].item
+    }
+
 }
 
 #[cfg(test)]
@@ -322,4 +674,230 @@ mod tests {
         sequencer.node_finished(key2);
         assert_eq!(0, sequencer.iter_active().count())
     }
+
+    #[test]
+    fn test_or_join_activates_on_first_parent() {
+        use crate::JoinMode;
+
+        let mut sequencer = Sequencer::default();
+        let key1 = sequencer.new_node(SeqItem::Walk);
+        let key2 = sequencer.new_node(SeqItem::Wait);
+        let child = sequencer.new_node_with_join(vec![key1, key2], SeqItem::Say, JoinMode::Any);
+
+        sequencer.drain_queue(|_key, _item| {});
+        sequencer.node_finished(key1);
+        assert_eq!(1, sequencer.queued_nodes.len());
+        assert_eq!(child, sequencer.queued_nodes[0]);
+
+        // The still-running sibling parent finishing later must not
+        // queue the child a second time.
+        sequencer.drain_queue(|_key, _item| {});
+        sequencer.node_finished(key2);
+        assert_eq!(0, sequencer.queued_nodes.len());
+    }
+
+    #[test]
+    fn test_and_join_waits_for_all_parents() {
+        use crate::JoinMode;
+
+        let mut sequencer = Sequencer::default();
+        let key1 = sequencer.new_node(SeqItem::Walk);
+        let key2 = sequencer.new_node(SeqItem::Wait);
+        let child = sequencer.new_node_with_join(vec![key1, key2], SeqItem::Say, JoinMode::All);
+
+        sequencer.drain_queue(|_key, _item| {});
+        sequencer.node_finished(key1);
+        assert_eq!(0, sequencer.queued_nodes.len());
+        sequencer.node_finished(key2);
+        assert_eq!(1, sequencer.queued_nodes.len());
+        assert_eq!(child, sequencer.queued_nodes[0]);
+    }
+
+    #[test]
+    fn test_add_edge_rejects_direct_cycle() {
+        let mut sequencer = Sequencer::default();
+        let a = sequencer.new_node(SeqItem::Walk);
+        let b = sequencer.create_node_with_parents(vec![a], SeqItem::Wait);
+        assert_eq!(Err(crate::CycleError), sequencer.add_edge(b, a));
+    }
+
+    #[test]
+    fn test_add_edge_rejects_self_loop() {
+        let mut sequencer = Sequencer::default();
+        let a = sequencer.new_node(SeqItem::Walk);
+        assert_eq!(Err(crate::CycleError), sequencer.add_edge(a, a));
+    }
+
+    #[test]
+    fn test_add_edge_rejects_indirect_cycle() {
+        let mut sequencer = Sequencer::default();
+        let a = sequencer.new_node(SeqItem::Walk);
+        let b = sequencer.create_node_with_parents(vec![a], SeqItem::Wait);
+        let c = sequencer.create_node_with_parents(vec![b], SeqItem::Say);
+        // a -> b -> c already exists, so c -> a would close the loop.
+        assert_eq!(Err(crate::CycleError), sequencer.add_edge(c, a));
+    }
+
+    #[test]
+    fn test_add_edge_allows_diamond() {
+        use crate::JoinMode;
+
+        let mut sequencer = Sequencer::default();
+        let a = sequencer.new_node(SeqItem::Walk);
+        let b = sequencer.create_node_with_parents(vec![a], SeqItem::Wait);
+        let c = sequencer.create_node_with_parents(vec![a], SeqItem::Say);
+        let join = sequencer.new_node_with_join(vec![b], SeqItem::Walk, JoinMode::All);
+        assert!(sequencer.add_edge(c, join).is_ok());
+    }
+
+    #[test]
+    fn test_cancel_marks_status_and_unqueues() {
+        let mut sequencer = Sequencer::default();
+        let key = sequencer.new_node(SeqItem::Walk);
+        sequencer.cancel(key);
+        assert_eq!(NodeStatus::Cancelled, sequencer.nodes[key].status);
+        assert_eq!(0, sequencer.queued_nodes.len());
+    }
+
+    #[test]
+    fn test_cancel_subtree_prunes_descendants() {
+        let mut sequencer = Sequencer::default();
+        let root = sequencer.new_node(SeqItem::Walk);
+        let child = sequencer.create_node_with_parents(vec![root], SeqItem::Wait);
+        let grandchild = sequencer.create_node_with_parents(vec![child], SeqItem::Say);
+
+        sequencer.cancel_subtree(root);
+
+        assert!(!sequencer.nodes.contains_key(root));
+        assert!(!sequencer.nodes.contains_key(child));
+        assert!(!sequencer.nodes.contains_key(grandchild));
+        assert_eq!(0, sequencer.roots.len());
+        assert_eq!(0, sequencer.queued_nodes.len());
+    }
+
+    #[test]
+    fn test_cancel_subtree_preserves_shared_descendant() {
+        use crate::JoinMode;
+
+        let mut sequencer = Sequencer::default();
+        let a = sequencer.new_node(SeqItem::Walk);
+        let b = sequencer.new_node(SeqItem::Wait);
+        // `join` has two parents: one inside the cancelled branch, one
+        // outside it, so it must survive.
+        let join = sequencer.new_node_with_join(vec![a, b], SeqItem::Say, JoinMode::Any);
+
+        sequencer.cancel_subtree(a);
+
+        assert!(!sequencer.nodes.contains_key(a));
+        assert!(sequencer.nodes.contains_key(b));
+        assert!(sequencer.nodes.contains_key(join));
+        assert_eq!(vec![b], sequencer.nodes[join].parents);
+    }
+
+    #[test]
+    fn test_cancel_subtree_reclaims_reach_rows() {
+        let mut sequencer = Sequencer::default();
+
+        for _ in 0..50 {
+            let root = sequencer.new_node(SeqItem::Walk);
+            let child = sequencer.create_node_with_parents(vec![root], SeqItem::Wait);
+            sequencer.create_node_with_parents(vec![child], SeqItem::Say);
+            sequencer.cancel_subtree(root);
+        }
+
+        // Repeated cancel/create cycles must reuse freed rows rather than
+        // growing `reach` without bound.
+        assert!(sequencer.reach.len() <= 3);
+    }
+
+    #[test]
+    fn test_cancel_subtree_does_not_leave_stale_reachability() {
+        let mut sequencer = Sequencer::default();
+        let w = sequencer.new_node(SeqItem::Walk);
+        let u = sequencer.create_node_with_parents(vec![w], SeqItem::Wait);
+        let y = sequencer.new_node(SeqItem::Wait);
+        // `x` survives `cancel_subtree(u)` via its other parent `y`, and
+        // `w`'s only path to `x` went through the now-deleted `u`.
+        let x = sequencer.create_node_with_parents(vec![u, y], SeqItem::Say);
+
+        sequencer.cancel_subtree(u);
+
+        assert!(sequencer.nodes.contains_key(x));
+        sequencer
+            .add_edge(x, w)
+            .expect("w no longer reaches x now that the only path through u is gone");
+    }
+
+    #[test]
+    fn test_node_finished_with_skips_unselected_children() {
+        let mut sequencer = Sequencer::default();
+        let root = sequencer.new_node(SeqItem::Walk);
+        let chosen = sequencer.create_node_with_parents(vec![root], SeqItem::Say);
+        let rejected = sequencer.create_node_with_parents(vec![root], SeqItem::Wait);
+
+        sequencer.drain_queue(|_, _| {});
+        sequencer.node_finished_with(root, |node| node.key == chosen);
+
+        assert_eq!(NodeStatus::Skipped, sequencer.nodes[rejected].status);
+        assert_eq!(1, sequencer.queued_nodes.len());
+        assert_eq!(chosen, sequencer.queued_nodes[0]);
+    }
+
+    #[test]
+    fn test_node_finished_with_cascades_skip_to_rejected_branchs_children() {
+        let mut sequencer = Sequencer::default();
+        let root = sequencer.new_node(SeqItem::Walk);
+        let chosen = sequencer.create_node_with_parents(vec![root], SeqItem::Say);
+        let rejected = sequencer.create_node_with_parents(vec![root], SeqItem::Wait);
+        let rejected_child = sequencer.create_node_with_parents(vec![rejected], SeqItem::Walk);
+
+        sequencer.drain_queue(|_, _| {});
+        sequencer.node_finished_with(root, |node| node.key == chosen);
+
+        // `rejected`'s only parent into `rejected_child` never actually ran,
+        // so the unselected branch must not execute downstream either.
+        assert_eq!(NodeStatus::Skipped, sequencer.nodes[rejected].status);
+        assert_eq!(NodeStatus::Skipped, sequencer.nodes[rejected_child].status);
+        assert_eq!(1, sequencer.queued_nodes.len());
+        assert_eq!(chosen, sequencer.queued_nodes[0]);
+    }
+
+    #[test]
+    fn test_skipped_parent_satisfies_and_join() {
+        let mut sequencer = Sequencer::default();
+        let root = sequencer.new_node(SeqItem::Walk);
+        let chosen = sequencer.create_node_with_parents(vec![root], SeqItem::Say);
+        let rejected = sequencer.create_node_with_parents(vec![root], SeqItem::Wait);
+        let join = sequencer.create_node_with_parents(vec![chosen, rejected], SeqItem::Walk);
+
+        sequencer.drain_queue(|_, _| {});
+        sequencer.node_finished_with(root, |node| node.key == chosen);
+        sequencer.drain_queue(|_, _| {});
+        sequencer.node_finished(chosen);
+
+        assert_eq!(1, sequencer.queued_nodes.len());
+        assert_eq!(join, sequencer.queued_nodes[0]);
+    }
+
+    #[test]
+    fn test_node_finished_with_does_not_skip_child_already_active_via_other_parent() {
+        use crate::JoinMode;
+
+        let mut sequencer = Sequencer::default();
+        let p1 = sequencer.new_node(SeqItem::Walk);
+        let p2 = sequencer.new_node(SeqItem::Wait);
+        let child = sequencer.new_node_with_join(vec![p1, p2], SeqItem::Say, JoinMode::Any);
+
+        sequencer.drain_queue(|_, _| {});
+        sequencer.node_finished(p1);
+        // The `Any`-join already queued and drained `child` on `p1` alone.
+        sequencer.drain_queue(|_, _| {});
+        assert_eq!(NodeStatus::Active, sequencer.nodes[child].status);
+
+        sequencer.node_finished_with(p2, |node| node.key != child);
+
+        // `child` is genuinely running and must not be clobbered.
+        assert_eq!(NodeStatus::Active, sequencer.nodes[child].status);
+        assert_eq!(1, sequencer.iter_active().count());
+    }
 }